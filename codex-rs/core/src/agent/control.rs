@@ -41,7 +41,9 @@ impl AgentControl {
         prompt: String,
     ) -> CodexResult<ThreadId> {
         let state = self.upgrade()?;
-        let reservation = self.state.reserve_spawn_slot(config.agent_max_threads)?;
+        let reservation = self
+            .state
+            .reserve_spawn_slot(config.agent_max_threads, config.agent_max_total_threads)?;
 
         // The same `AgentControl` is sent to spawn the thread.
         let new_thread = state.spawn_new_thread(config, self.clone()).await?;
@@ -473,6 +475,43 @@ mod tests {
             .expect("shutdown agent");
     }
 
+    #[tokio::test]
+    async fn spawn_agent_respects_max_total_threads_after_shutdown() {
+        let max_total_threads = 1usize;
+        let (_home, config) = test_config_with_cli_overrides(vec![(
+            "agents.max_total_threads".to_string(),
+            TomlValue::Integer(max_total_threads as i64),
+        )])
+        .await;
+        let manager = ThreadManager::with_models_provider_and_home(
+            CodexAuth::from_api_key("dummy"),
+            config.model_provider.clone(),
+            config.codex_home.clone(),
+        );
+        let control = manager.agent_control();
+
+        let first_agent_id = control
+            .spawn_agent(config.clone(), "hello".to_string())
+            .await
+            .expect("spawn_agent should succeed");
+        let _ = control
+            .shutdown_agent(first_agent_id)
+            .await
+            .expect("shutdown agent");
+
+        let err = control
+            .spawn_agent(config, "hello again".to_string())
+            .await
+            .expect_err("spawn_agent should respect max total threads");
+        let CodexErr::AgentSpawnLimitReached {
+            max_total_threads: seen_max_total_threads,
+        } = err
+        else {
+            panic!("expected CodexErr::AgentSpawnLimitReached");
+        };
+        assert_eq!(seen_max_total_threads, max_total_threads);
+    }
+
     #[tokio::test]
     async fn spawn_agent_limit_shared_across_clones() {
         let max_threads = 1usize;
@@ -9,7 +9,8 @@ use std::sync::atomic::Ordering;
 
 /// This structure is used to add some limits on the multi-agent capabilities for Codex. In
 /// the current implementation, it limits:
-/// * Total number of sub-agents (i.e. threads) per user session
+/// * Total number of sub-agents (i.e. threads) open at the same time per user session
+/// * Total number of sub-agents spawned over the lifetime of a user session
 ///
 /// This structure is shared by all agents in the same user session (because the `AgentControl`
 /// is).
@@ -17,20 +18,32 @@ use std::sync::atomic::Ordering;
 pub(crate) struct Guards {
     threads_set: Mutex<HashSet<ThreadId>>,
     total_count: AtomicUsize,
+    /// Number of threads ever spawned. Unlike `total_count`, this is never decremented when a
+    /// thread is released.
+    spawned_count: AtomicUsize,
 }
 
 impl Guards {
     pub(crate) fn reserve_spawn_slot(
         self: &Arc<Self>,
         max_threads: Option<usize>,
+        max_total_threads: Option<usize>,
     ) -> Result<SpawnReservation> {
         if let Some(max_threads) = max_threads {
-            if !self.try_increment_spawned(max_threads) {
+            if !try_increment(&self.total_count, max_threads) {
                 return Err(CodexErr::AgentLimitReached { max_threads });
             }
         } else {
             self.total_count.fetch_add(1, Ordering::AcqRel);
         }
+        if let Some(max_total_threads) = max_total_threads {
+            if !try_increment(&self.spawned_count, max_total_threads) {
+                self.total_count.fetch_sub(1, Ordering::AcqRel);
+                return Err(CodexErr::AgentSpawnLimitReached { max_total_threads });
+            }
+        } else {
+            self.spawned_count.fetch_add(1, Ordering::AcqRel);
+        }
         Ok(SpawnReservation {
             state: Arc::clone(self),
             active: true,
@@ -57,22 +70,22 @@ impl Guards {
             .unwrap_or_else(std::sync::PoisonError::into_inner);
         threads.insert(thread_id);
     }
+}
 
-    fn try_increment_spawned(&self, max_threads: usize) -> bool {
-        let mut current = self.total_count.load(Ordering::Acquire);
-        loop {
-            if current >= max_threads {
-                return false;
-            }
-            match self.total_count.compare_exchange_weak(
-                current,
-                current + 1,
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            ) {
-                Ok(_) => return true,
-                Err(updated) => current = updated,
-            }
+fn try_increment(counter: &AtomicUsize, max: usize) -> bool {
+    let mut current = counter.load(Ordering::Acquire);
+    loop {
+        if current >= max {
+            return false;
+        }
+        match counter.compare_exchange_weak(
+            current,
+            current + 1,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => return true,
+            Err(updated) => current = updated,
         }
     }
 }
@@ -93,6 +106,7 @@ impl Drop for SpawnReservation {
     fn drop(&mut self) {
         if self.active {
             self.state.total_count.fetch_sub(1, Ordering::AcqRel);
+            self.state.spawned_count.fetch_sub(1, Ordering::AcqRel);
         }
     }
 }
@@ -105,21 +119,27 @@ mod tests {
     #[test]
     fn reservation_drop_releases_slot() {
         let guards = Arc::new(Guards::default());
-        let reservation = guards.reserve_spawn_slot(Some(1)).expect("reserve slot");
+        let reservation = guards
+            .reserve_spawn_slot(Some(1), None)
+            .expect("reserve slot");
         drop(reservation);
 
-        let reservation = guards.reserve_spawn_slot(Some(1)).expect("slot released");
+        let reservation = guards
+            .reserve_spawn_slot(Some(1), None)
+            .expect("slot released");
         drop(reservation);
     }
 
     #[test]
     fn commit_holds_slot_until_release() {
         let guards = Arc::new(Guards::default());
-        let reservation = guards.reserve_spawn_slot(Some(1)).expect("reserve slot");
+        let reservation = guards
+            .reserve_spawn_slot(Some(1), None)
+            .expect("reserve slot");
         let thread_id = ThreadId::new();
         reservation.commit(thread_id);
 
-        let err = match guards.reserve_spawn_slot(Some(1)) {
+        let err = match guards.reserve_spawn_slot(Some(1), None) {
             Ok(_) => panic!("limit should be enforced"),
             Err(err) => err,
         };
@@ -130,7 +150,7 @@ mod tests {
 
         guards.release_spawned_thread(thread_id);
         let reservation = guards
-            .reserve_spawn_slot(Some(1))
+            .reserve_spawn_slot(Some(1), None)
             .expect("slot released after thread removal");
         drop(reservation);
     }
@@ -138,13 +158,15 @@ mod tests {
     #[test]
     fn release_ignores_unknown_thread_id() {
         let guards = Arc::new(Guards::default());
-        let reservation = guards.reserve_spawn_slot(Some(1)).expect("reserve slot");
+        let reservation = guards
+            .reserve_spawn_slot(Some(1), None)
+            .expect("reserve slot");
         let thread_id = ThreadId::new();
         reservation.commit(thread_id);
 
         guards.release_spawned_thread(ThreadId::new());
 
-        let err = match guards.reserve_spawn_slot(Some(1)) {
+        let err = match guards.reserve_spawn_slot(Some(1), None) {
             Ok(_) => panic!("limit should still be enforced"),
             Err(err) => err,
         };
@@ -155,7 +177,7 @@ mod tests {
 
         guards.release_spawned_thread(thread_id);
         let reservation = guards
-            .reserve_spawn_slot(Some(1))
+            .reserve_spawn_slot(Some(1), None)
             .expect("slot released after real thread removal");
         drop(reservation);
     }
@@ -163,19 +185,23 @@ mod tests {
     #[test]
     fn release_is_idempotent_for_registered_threads() {
         let guards = Arc::new(Guards::default());
-        let reservation = guards.reserve_spawn_slot(Some(1)).expect("reserve slot");
+        let reservation = guards
+            .reserve_spawn_slot(Some(1), None)
+            .expect("reserve slot");
         let first_id = ThreadId::new();
         reservation.commit(first_id);
 
         guards.release_spawned_thread(first_id);
 
-        let reservation = guards.reserve_spawn_slot(Some(1)).expect("slot reused");
+        let reservation = guards
+            .reserve_spawn_slot(Some(1), None)
+            .expect("slot reused");
         let second_id = ThreadId::new();
         reservation.commit(second_id);
 
         guards.release_spawned_thread(first_id);
 
-        let err = match guards.reserve_spawn_slot(Some(1)) {
+        let err = match guards.reserve_spawn_slot(Some(1), None) {
             Ok(_) => panic!("limit should still be enforced"),
             Err(err) => err,
         };
@@ -186,8 +212,65 @@ mod tests {
 
         guards.release_spawned_thread(second_id);
         let reservation = guards
-            .reserve_spawn_slot(Some(1))
+            .reserve_spawn_slot(Some(1), None)
             .expect("slot released after second thread removal");
         drop(reservation);
     }
+
+    #[test]
+    fn reservation_drop_releases_total_slot() {
+        let guards = Arc::new(Guards::default());
+        let reservation = guards
+            .reserve_spawn_slot(None, Some(1))
+            .expect("reserve slot");
+        drop(reservation);
+
+        let reservation = guards
+            .reserve_spawn_slot(None, Some(1))
+            .expect("uncommitted reservation does not count towards the total");
+        drop(reservation);
+    }
+
+    #[test]
+    fn total_limit_counts_released_threads() {
+        let guards = Arc::new(Guards::default());
+        let reservation = guards
+            .reserve_spawn_slot(None, Some(1))
+            .expect("reserve slot");
+        let thread_id = ThreadId::new();
+        reservation.commit(thread_id);
+        guards.release_spawned_thread(thread_id);
+
+        let err = match guards.reserve_spawn_slot(None, Some(1)) {
+            Ok(_) => panic!("total limit should be enforced"),
+            Err(err) => err,
+        };
+        let CodexErr::AgentSpawnLimitReached { max_total_threads } = err else {
+            panic!("expected CodexErr::AgentSpawnLimitReached");
+        };
+        assert_eq!(max_total_threads, 1);
+    }
+
+    #[test]
+    fn total_limit_rejection_releases_concurrent_slot() {
+        let guards = Arc::new(Guards::default());
+        let reservation = guards
+            .reserve_spawn_slot(Some(2), Some(1))
+            .expect("reserve slot");
+        reservation.commit(ThreadId::new());
+
+        let err = match guards.reserve_spawn_slot(Some(2), Some(1)) {
+            Ok(_) => panic!("total limit should be enforced"),
+            Err(err) => err,
+        };
+        let CodexErr::AgentSpawnLimitReached { max_total_threads } = err else {
+            panic!("expected CodexErr::AgentSpawnLimitReached");
+        };
+        assert_eq!(max_total_threads, 1);
+
+        let reservation = guards
+            .reserve_spawn_slot(Some(2), None)
+            .expect("rejected reservation should not hold a concurrent slot");
+        drop(reservation);
+    }
 }
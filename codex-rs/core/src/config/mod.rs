@@ -303,6 +303,9 @@ pub struct Config {
     /// Maximum number of agent threads that can be open concurrently.
     pub agent_max_threads: Option<usize>,
 
+    /// Maximum number of agent threads that can be spawned over the lifetime of a session.
+    pub agent_max_total_threads: Option<usize>,
+
     /// Directory containing all Codex state (defaults to `~/.codex` but can be
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
@@ -1047,6 +1050,12 @@ pub struct AgentsToml {
     /// When unset, no limit is enforced.
     #[schemars(range(min = 1))]
     pub max_threads: Option<usize>,
+
+    /// Maximum number of agent threads that can be spawned over the lifetime of a session,
+    /// including threads that have since been closed.
+    /// When unset, no limit is enforced.
+    #[schemars(range(min = 1))]
+    pub max_total_threads: Option<usize>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -1419,6 +1428,16 @@ impl Config {
                 "agents.max_threads must be at least 1",
             ));
         }
+        let agent_max_total_threads = cfg
+            .agents
+            .as_ref()
+            .and_then(|agents| agents.max_total_threads);
+        if agent_max_total_threads == Some(0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "agents.max_total_threads must be at least 1",
+            ));
+        }
 
         let ghost_snapshot = {
             let mut config = GhostSnapshotConfig::default();
@@ -1559,6 +1578,7 @@ impl Config {
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
             agent_max_threads,
+            agent_max_total_threads,
             codex_home,
             config_layer_stack,
             history,
@@ -3748,6 +3768,7 @@ model_verbosity = "high"
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
                 agent_max_threads: None,
+                agent_max_total_threads: None,
                 codex_home: fixture.codex_home(),
                 config_layer_stack: Default::default(),
                 history: History::default(),
@@ -3837,6 +3858,7 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: None,
+            agent_max_total_threads: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
@@ -3941,6 +3963,7 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: None,
+            agent_max_total_threads: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
@@ -4031,6 +4054,7 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: None,
+            agent_max_total_threads: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
@@ -81,6 +81,9 @@ pub enum CodexErr {
     #[error("agent thread limit reached (max {max_threads})")]
     AgentLimitReached { max_threads: usize },
 
+    #[error("agent spawn limit reached (max {max_total_threads} per session)")]
+    AgentSpawnLimitReached { max_total_threads: usize },
+
     #[error("session configured event was not the first event in the stream")]
     SessionConfiguredNotFirstEvent,
 
@@ -203,6 +206,7 @@ impl CodexErr {
             | CodexErr::ContextWindowExceeded
             | CodexErr::ThreadNotFound(_)
             | CodexErr::AgentLimitReached { .. }
+            | CodexErr::AgentSpawnLimitReached { .. }
             | CodexErr::Spawn
             | CodexErr::SessionConfiguredNotFirstEvent
             | CodexErr::UsageLimitReached(_) => false,
@@ -503,7 +507,8 @@ impl CodexErr {
             | CodexErr::InternalAgentDied => CodexErrorInfo::InternalServerError,
             CodexErr::UnsupportedOperation(_)
             | CodexErr::ThreadNotFound(_)
-            | CodexErr::AgentLimitReached { .. } => CodexErrorInfo::BadRequest,
+            | CodexErr::AgentLimitReached { .. }
+            | CodexErr::AgentSpawnLimitReached { .. } => CodexErrorInfo::BadRequest,
             CodexErr::Sandbox(_) => CodexErrorInfo::SandboxError,
             _ => CodexErrorInfo::Other,
         }